@@ -0,0 +1,224 @@
+//! A thin layer over `cargo metadata`, modeled on rust-analyzer's `cargo_workspace`
+//! module. This lets the rest of the crate talk about packages and targets instead
+//! of shelling out to Cargo and re-parsing JSON wherever it needs to know what it's
+//! documenting.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json;
+use serde_json::Value;
+
+/// The kind of a Cargo target, as reported by `cargo metadata`'s `kind` array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Other,
+}
+
+impl TargetKind {
+    fn from_cargo_metadata(kinds: &[String]) -> TargetKind {
+        match kinds.get(0).map(|s| s.as_str()) {
+            Some("lib") | Some("rlib") | Some("dylib") | Some("proc-macro") => TargetKind::Lib,
+            Some("bin") => TargetKind::Bin,
+            _ => TargetKind::Other,
+        }
+    }
+}
+
+/// A single build target (library, binary, ...) belonging to a `Package`.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub name: String,
+    pub kind: TargetKind,
+    pub edition: String,
+    /// Path to the crate root source file (`lib.rs`, `main.rs`, ...).
+    pub src_path: PathBuf,
+}
+
+/// A single Cargo package, as reported by `cargo metadata`.
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub id: String,
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub targets: Vec<Target>,
+}
+
+impl Package {
+    /// The package's library target, if it has one. This is the target whose
+    /// analysis data we want to document.
+    pub fn lib_target(&self) -> Option<&Target> {
+        self.targets.iter().find(|target| target.kind == TargetKind::Lib)
+    }
+}
+
+/// One entry in `cargo metadata`'s dependency resolution graph: a package id
+/// together with the ids of the packages it directly depends on.
+#[derive(Debug, Clone)]
+pub struct ResolveNode {
+    pub id: String,
+    pub dependencies: Vec<String>,
+}
+
+/// The resolved dependency graph for the whole workspace.
+#[derive(Debug, Clone)]
+pub struct Resolve {
+    pub nodes: Vec<ResolveNode>,
+}
+
+/// The result of running `cargo metadata` against a workspace: the full set of
+/// packages that make it up, with enough information to find each one's root
+/// analysis def.
+#[derive(Debug, Clone)]
+pub struct CargoWorkspace {
+    pub packages: Vec<Package>,
+    pub workspace_root: PathBuf,
+    pub resolve: Option<Resolve>,
+    /// Ids of the packages that are members of this workspace, as opposed to
+    /// the external dependencies `cargo metadata` also reports in `packages`.
+    workspace_members: Vec<String>,
+}
+
+impl CargoWorkspace {
+    /// Shells out to `cargo metadata` against the `Cargo.toml` inside the
+    /// directory `manifest_path` and parses the result.
+    pub fn from_cargo_metadata(
+        manifest_path: &Path,
+    ) -> Result<CargoWorkspace, Box<std::error::Error>> {
+        let mut command = Command::new("cargo");
+        command
+            .arg("metadata")
+            .arg("--format-version=1")
+            .arg("--manifest-path")
+            .arg(manifest_path.join("Cargo.toml"));
+
+        let output = command.output()?;
+
+        if !output.status.success() {
+            return Err(
+                format!(
+                    "cargo metadata failed with status {}. stderr:\n{}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr)
+                ).into(),
+            );
+        }
+
+        let json: Value = serde_json::from_slice(&output.stdout)?;
+        CargoWorkspace::from_json(&json)
+    }
+
+    fn from_json(json: &Value) -> Result<CargoWorkspace, Box<std::error::Error>> {
+        let workspace_root = match json["workspace_root"].as_str() {
+            Some(root) => PathBuf::from(root),
+            None => return Err("cargo metadata output is missing \"workspace_root\"".into()),
+        };
+
+        let mut packages = Vec::new();
+
+        let raw_packages = json["packages"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        for package in raw_packages {
+            let mut targets = Vec::new();
+
+            let raw_targets = package["targets"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+            for target in raw_targets {
+                let mut kinds = Vec::new();
+                if let Some(raw_kinds) = target["kind"].as_array() {
+                    for kind in raw_kinds {
+                        if let Some(kind) = kind.as_str() {
+                            kinds.push(kind.to_string());
+                        }
+                    }
+                }
+
+                targets.push(Target {
+                    name: target["name"].as_str().unwrap_or_default().to_string(),
+                    kind: TargetKind::from_cargo_metadata(&kinds),
+                    edition: target["edition"].as_str().unwrap_or("2015").to_string(),
+                    src_path: PathBuf::from(target["src_path"].as_str().unwrap_or_default()),
+                });
+            }
+
+            packages.push(Package {
+                id: package["id"].as_str().unwrap_or_default().to_string(),
+                name: package["name"].as_str().unwrap_or_default().to_string(),
+                manifest_path: PathBuf::from(
+                    package["manifest_path"].as_str().unwrap_or_default(),
+                ),
+                targets,
+            });
+        }
+
+        let resolve = match json["resolve"].as_object() {
+            Some(_) => {
+                let mut nodes = Vec::new();
+
+                let raw_nodes = json["resolve"]["nodes"].as_array().map(Vec::as_slice).unwrap_or(
+                    &[],
+                );
+                for node in raw_nodes {
+                    let mut dependencies = Vec::new();
+                    if let Some(raw_deps) = node["dependencies"].as_array() {
+                        for dep in raw_deps {
+                            if let Some(dep) = dep.as_str() {
+                                dependencies.push(dep.to_string());
+                            }
+                        }
+                    }
+
+                    nodes.push(ResolveNode {
+                        id: node["id"].as_str().unwrap_or_default().to_string(),
+                        dependencies,
+                    });
+                }
+
+                Some(Resolve { nodes })
+            }
+            None => None,
+        };
+
+        let mut workspace_members = Vec::new();
+        if let Some(raw_members) = json["workspace_members"].as_array() {
+            for member in raw_members {
+                if let Some(member) = member.as_str() {
+                    workspace_members.push(member.to_string());
+                }
+            }
+        }
+
+        Ok(CargoWorkspace { packages, workspace_root, resolve, workspace_members })
+    }
+
+    /// The packages that are members of this workspace, excluding the external
+    /// dependencies `cargo metadata` also returns in `packages`. This is what
+    /// callers should iterate over to document "every crate in the workspace",
+    /// as opposed to every crate in the full dependency graph.
+    pub fn workspace_packages(&self) -> Vec<&Package> {
+        self.packages
+            .iter()
+            .filter(|package| self.workspace_members.iter().any(|id| id == &package.id))
+            .collect()
+    }
+
+    /// The direct dependency packages of `package_id`, as reported by cargo's
+    /// dependency resolution graph.
+    pub fn dependencies(&self, package_id: &str) -> Vec<&Package> {
+        let resolve = match self.resolve {
+            Some(ref resolve) => resolve,
+            None => return Vec::new(),
+        };
+
+        let node = resolve.nodes.iter().find(|node| node.id == package_id);
+        let dependencies = match node {
+            Some(node) => &node.dependencies,
+            None => return Vec::new(),
+        };
+
+        dependencies
+            .iter()
+            .filter_map(|dep_id| self.packages.iter().find(|package| &package.id == dep_id))
+            .collect()
+    }
+}
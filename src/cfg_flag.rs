@@ -0,0 +1,44 @@
+//! A single `--cfg` flag, modeled on rust-analyzer's `CfgFlag`. Flags come in two
+//! forms: a bare atom (`foo`) or a key/value pair (`foo="bar"`), matching what
+//! `rustc --cfg` accepts.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgFlag {
+    Atom(String),
+    KeyValue { key: String, value: String },
+}
+
+impl FromStr for CfgFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CfgFlag, String> {
+        match s.find('=') {
+            Some(index) => {
+                let key = &s[..index];
+                let value = &s[index + 1..];
+                if !(value.starts_with('"') && value.ends_with('"')) {
+                    return Err(format!("invalid cfg flag: {:?} (expected foo=\"bar\")", s));
+                }
+                let value = &value[1..value.len() - 1];
+
+                Ok(CfgFlag::KeyValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+            }
+            None => Ok(CfgFlag::Atom(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for CfgFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CfgFlag::Atom(ref name) => write!(f, "{}", name),
+            CfgFlag::KeyValue { ref key, ref value } => write!(f, "{}=\"{}\"", key, value),
+        }
+    }
+}
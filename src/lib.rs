@@ -2,6 +2,10 @@ extern crate jsonapi;
 extern crate rls_analysis as analysis;
 extern crate serde_json;
 
+mod cargo_workspace;
+mod cfg_flag;
+mod project_json;
+
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::fmt;
@@ -12,6 +16,29 @@ use std::process::Command;
 
 use analysis::raw::DefKind;
 
+use cargo_workspace::CargoWorkspace;
+use cfg_flag::CfgFlag;
+use project_json::ProjectJson;
+
+/// Cargo-specific knobs for `generate_analysis`: which features to build with
+/// and any extra `--cfg` flags to pass to rustc. Modeled on rust-analyzer's
+/// `CargoConfig`.
+#[derive(Debug, Clone, Default)]
+pub struct CargoConfig {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    pub cfg: Vec<CfgFlag>,
+}
+
+/// Where the crate graph we're documenting comes from: a Cargo workspace (the
+/// common case) or a `rust-project.json` describing crates directly, for build
+/// systems other than Cargo.
+pub enum ProjectWorkspace {
+    Cargo(CargoWorkspace),
+    Json(ProjectJson),
+}
+
 #[derive(Debug)]
 pub struct CrateErr {
     error: String,
@@ -39,6 +66,9 @@ pub struct Config {
     manifest_path: PathBuf,
     host: analysis::AnalysisHost,
     assets: Vec<Asset>,
+    workspace: ProjectWorkspace,
+    cargo_config: CargoConfig,
+    document_dependencies: bool,
 }
 
 /// Static assets compiled into the binary so we get a single executable.
@@ -51,6 +81,42 @@ struct Asset {
 
 impl Config {
     pub fn new(manifest_path: PathBuf) -> Result<Config, Box<std::error::Error>> {
+        let workspace = CargoWorkspace::from_cargo_metadata(&manifest_path)?;
+
+        Config::with_workspace(manifest_path, ProjectWorkspace::Cargo(workspace))
+    }
+
+    /// Builds a `Config` for a project described by a `rust-project.json` file
+    /// rather than a `Cargo.toml`, for build systems other than Cargo.
+    pub fn from_project_json(
+        manifest_path: PathBuf,
+        project_json_path: &Path,
+    ) -> Result<Config, Box<std::error::Error>> {
+        let project = ProjectJson::from_path(project_json_path)?;
+
+        Config::with_workspace(manifest_path, ProjectWorkspace::Json(project))
+    }
+
+    /// Overrides the default feature/cfg selection used when building the
+    /// crate's save-analysis data. Only meaningful for a `Cargo` workspace.
+    pub fn with_cargo_config(mut self, cargo_config: CargoConfig) -> Config {
+        self.cargo_config = cargo_config;
+        self
+    }
+
+    /// Opts into also documenting the direct dependencies of each crate that
+    /// gets documented. Only meaningful for a `Cargo` workspace: a
+    /// `rust-project.json` project documents every crate it lists and always
+    /// links each one to the crates in its `deps`.
+    pub fn with_dependencies(mut self, document_dependencies: bool) -> Config {
+        self.document_dependencies = document_dependencies;
+        self
+    }
+
+    fn with_workspace(
+        manifest_path: PathBuf,
+        workspace: ProjectWorkspace,
+    ) -> Result<Config, Box<std::error::Error>> {
         let host = analysis::AnalysisHost::new(analysis::Target::Debug);
 
         let assets = vec![
@@ -96,33 +162,68 @@ impl Config {
             manifest_path,
             host,
             assets,
+            workspace,
+            cargo_config: CargoConfig::default(),
+            document_dependencies: false,
         })
     }
 }
 
 
 pub fn build(config: &Config, artifacts: &[&str]) -> Result<(), Box<std::error::Error>> {
+    // reloads analysis for the whole workspace in one go, so every member package's
+    // defs are available below without shelling out to Cargo again per-crate.
     generate_analysis(config)?;
 
-    let data = DocData::new(config)?;
-
     let output_path = config.manifest_path.join("target/doc");
     fs::create_dir_all(&output_path)?;
 
     let mut stdout = io::stdout();
 
     if artifacts.contains(&"json") {
-        print!("generating JSON...");
-        stdout.flush()?;
+        match config.workspace {
+            ProjectWorkspace::Cargo(ref cargo) => {
+                for package in cargo.workspace_packages() {
+                    let target = match package.lib_target() {
+                        Some(target) => target,
+                        // skip packages with no library target; there's nothing to document
+                        None => continue,
+                    };
 
-        let json = data.to_json(config)?;
+                    let deps = if config.document_dependencies {
+                        document_dependencies(config, cargo, package, &output_path)?
+                    } else {
+                        Vec::new()
+                    };
 
-        let mut json_path = output_path.clone();
-        json_path.push("data.json");
+                    print!("generating JSON for {}...", target.name);
+                    stdout.flush()?;
 
-        let mut file = File::create(json_path)?;
-        file.write_all(json.as_bytes())?;
-        println!("done.");
+                    let data = DocData::new(config, &target.name, deps)?;
+                    write_crate_json(&data, config, &output_path, &target.name)?;
+                    println!("done.");
+                }
+            }
+            ProjectWorkspace::Json(ref project) => {
+                for krate in &project.crates {
+                    let crate_name = krate.name();
+
+                    let deps = krate
+                        .deps
+                        .iter()
+                        .filter_map(|dep| project.crates.get(dep.krate))
+                        .map(|dep_krate| dep_krate.name())
+                        .collect();
+
+                    print!("generating JSON for {}...", crate_name);
+                    stdout.flush()?;
+
+                    let data = DocData::new(config, &crate_name, deps)?;
+                    write_crate_json(&data, config, &output_path, &crate_name)?;
+                    println!("done.");
+                }
+            }
+        }
     }
 
     // now that we've written out the data, we can write out the rest of it
@@ -154,35 +255,137 @@ fn create_asset_file(name: &str, path: &Path, data: &str) -> Result<(), Box<std:
     Ok(())
 }
 
+fn write_crate_json(
+    data: &DocData,
+    config: &Config,
+    output_path: &Path,
+    crate_name: &str,
+) -> Result<(), Box<std::error::Error>> {
+    let json = data.to_json(config)?;
+
+    let crate_path = output_path.join(crate_name);
+    fs::create_dir_all(&crate_path)?;
+
+    let mut json_path = crate_path;
+    json_path.push("data.json");
+
+    let mut file = File::create(json_path)?;
+    file.write_all(json.as_bytes())?;
+
+    Ok(())
+}
+
+/// Builds and documents the direct dependencies of `package`, the way
+/// `ui_test`'s `build_dependencies` resolves each dependency's rmeta via
+/// `cargo metadata` and records its paths. Returns the names of the
+/// dependency crates that were documented, so the caller can link to them.
+fn document_dependencies(
+    config: &Config,
+    cargo: &CargoWorkspace,
+    package: &cargo_workspace::Package,
+    output_path: &Path,
+) -> Result<Vec<String>, Box<std::error::Error>> {
+    let mut stdout = io::stdout();
+    let mut dep_names = Vec::new();
+
+    for dep in cargo.dependencies(&package.id) {
+        let target = match dep.lib_target() {
+            Some(target) => target,
+            // skip dependencies with no library target; there's nothing to document
+            None => continue,
+        };
+
+        print!("generating save analysis data for dependency {}...", dep.name);
+        stdout.flush()?;
+
+        let mut command = Command::new("cargo");
+        command
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(&dep.manifest_path)
+            .env("RUSTFLAGS", "-Z save-analysis")
+            .env("CARGO_TARGET_DIR", config.manifest_path.join("target/rls"));
+
+        let output = command.output()?;
+
+        if !output.status.success() {
+            println!("");
+            return Err(
+                format!(
+                    "Cargo failed with status {}. stderr:\n{}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr)
+                ).into(),
+            );
+        }
+        println!("done.");
+
+        config.host.reload(&config.manifest_path, &config.manifest_path, true)?;
+
+        let data = DocData::new(config, &target.name, Vec::new())?;
+        write_crate_json(&data, config, output_path, &target.name)?;
+
+        dep_names.push(target.name.clone());
+    }
+
+    Ok(dep_names)
+}
+
 fn generate_analysis(config: &Config) -> Result<(), Box<std::error::Error>> {
-    let mut command = Command::new("cargo");
     let manifest_path = &config.manifest_path;
+    let mut stdout = io::stdout();
 
-    command
-        .arg("build")
-        .arg("--manifest-path")
-        .arg(manifest_path.join("Cargo.toml"))
-        .env("RUSTFLAGS", "-Z save-analysis")
-        .env("CARGO_TARGET_DIR", manifest_path.join("target/rls"));
+    // A `Json` workspace describes a project built by something other than
+    // Cargo, so the save-analysis data is assumed to already have been
+    // produced by that build system; we just load it. A `Cargo` workspace is
+    // the common case, where we drive the build ourselves.
+    if let ProjectWorkspace::Cargo(_) = config.workspace {
+        let mut command = Command::new("cargo");
 
-    let mut stdout = io::stdout();
+        command
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(manifest_path.join("Cargo.toml"));
 
-    print!("generating save analysis data...");
-    stdout.flush()?;
+        let cargo_config = &config.cargo_config;
 
-    let output = command.output()?;
+        if cargo_config.all_features {
+            command.arg("--all-features");
+        } else if !cargo_config.features.is_empty() {
+            command.arg("--features").arg(cargo_config.features.join(" "));
+        }
 
-    if !output.status.success() {
-        println!("");
-        return Err(
-            format!(
-                "Cargo failed with status {}. stderr:\n{}",
-                output.status,
-                String::from_utf8_lossy(&output.stderr)
-            ).into(),
-        );
+        if cargo_config.no_default_features {
+            command.arg("--no-default-features");
+        }
+
+        let mut rustflags = String::from("-Z save-analysis");
+        for flag in &cargo_config.cfg {
+            rustflags.push_str(" --cfg ");
+            rustflags.push_str(&flag.to_string());
+        }
+
+        command
+            .env("RUSTFLAGS", rustflags)
+            .env("CARGO_TARGET_DIR", manifest_path.join("target/rls"));
+
+        print!("generating save analysis data...");
+        stdout.flush()?;
+
+        let output = command.output()?;
+
+        if !output.status.success() {
+            println!("");
+            return Err(
+                format!(
+                    "Cargo failed with status {}. stderr:\n{}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr)
+                ).into(),
+            );
+        }
+        println!("done.");
     }
-    println!("done.");
 
     print!("loading save analysis data...");
     stdout.flush()?;
@@ -196,17 +399,24 @@ fn generate_analysis(config: &Config) -> Result<(), Box<std::error::Error>> {
 struct DocData {
     krate: Crate,
     data: HashMap<String, Item>,
+    /// Names of the crates this crate directly depends on: for a `Cargo`
+    /// workspace only if dependency documentation was requested via
+    /// `Config::with_dependencies`, for a `rust-project.json` project always.
+    deps: Vec<String>,
 }
 
 impl DocData {
-    fn new(config: &Config) -> Result<DocData, Box<std::error::Error>> {
+    fn new(
+        config: &Config,
+        root_name: &str,
+        deps: Vec<String>,
+    ) -> Result<DocData, Box<std::error::Error>> {
         let roots = config.host.def_roots()?;
 
-        // FIXME: this whole code shouldn't look for a precise crate.
-        let root_id = roots.iter().find(|&&(_, ref name)| name == "example");
+        let root_id = roots.iter().find(|&&(_, ref name)| name == root_name);
         let root_id = match root_id {
             Some(&(id, _)) => id,
-            _ => return Err(Box::new(CrateErr::new("example"))),
+            _ => return Err(Box::new(CrateErr::new(root_name))),
         };
 
         let root_def = config.host.get_def(root_id)?;
@@ -222,7 +432,7 @@ impl DocData {
 
         let data = DocData::build_data(config, root_id, &mut krate)?;
 
-        Ok(DocData { krate, data })
+        Ok(DocData { krate, data, deps })
     }
 
     fn build_data(
@@ -231,13 +441,60 @@ impl DocData {
         krate: &mut Crate,
     ) -> Result<HashMap<String, Item>, Box<std::error::Error>> {
         let mut data = HashMap::new();
+        let mut mod_queue = vec![(root_id, krate.name.clone(), "crate")];
+        let mut pending_methods = Vec::new();
+
+        // Walk the crate root and every module discovered below it, so items
+        // nested in submodules (`mod foo { .. }`) are picked up too, not just
+        // the crate root's direct children. Each queue entry carries the
+        // qualname/kind its own children should report as their parent.
+        while let Some((mod_id, parent, parent_kind)) = mod_queue.pop() {
+            DocData::build_mod_data(
+                config,
+                mod_id,
+                &parent,
+                parent_kind,
+                krate,
+                &mut data,
+                &mut mod_queue,
+                &mut pending_methods,
+            )?;
+        }
+
+        DocData::attach_methods(config, &mut data, pending_methods)?;
 
+        Ok(data)
+    }
+
+    /// Documents the direct children of a single module (the crate root or a
+    /// submodule), pushing any nested modules found onto `mod_queue` so the
+    /// caller can recurse into them in turn. `parent`/`parent_kind` describe
+    /// `mod_id` itself, and are what its non-module children report as their
+    /// own parent.
+    fn build_mod_data(
+        config: &Config,
+        mod_id: analysis::Id,
+        parent: &str,
+        parent_kind: &'static str,
+        krate: &mut Crate,
+        data: &mut HashMap<String, Item>,
+        mod_queue: &mut Vec<(analysis::Id, String, &'static str)>,
+        pending_methods: &mut Vec<analysis::raw::Def>,
+    ) -> Result<(), Box<std::error::Error>> {
         let defs = config.host.for_each_child_def(
-            root_id,
+            mod_id,
             |_, def| def.clone(),
         )?;
 
         for def in defs.iter() {
+            let meta = |signature: &str| ItemMeta {
+                name: def.name.clone(),
+                docs: def.docs.clone(),
+                signature: signature.to_string(),
+                parent: parent.to_string(),
+                parent_kind,
+            };
+
             match def.kind {
                 DefKind::Mod => {
                     data.insert(
@@ -248,93 +505,318 @@ impl DocData {
                         },
                     );
                     krate.modules.push(def.qualname.clone());
+                    mod_queue.push((def.id, def.qualname.clone(), "module"));
+                }
+                DefKind::Struct => {
+                    let fields = DocData::child_names(config, def.id, DefKind::Field)?;
+                    data.insert(
+                        def.qualname.clone(),
+                        Item::Struct {
+                            meta: meta(&def.value),
+                            fields,
+                            methods: Vec::new(),
+                        },
+                    );
+                }
+                DefKind::Union => {
+                    let fields = DocData::child_names(config, def.id, DefKind::Field)?;
+                    data.insert(
+                        def.qualname.clone(),
+                        Item::Union {
+                            meta: meta(&def.value),
+                            fields,
+                        },
+                    );
+                }
+                DefKind::Enum => {
+                    data.insert(def.qualname.clone(), Item::Enum { meta: meta(&def.value) });
                 }
-                DefKind::Static => (),
-                DefKind::Const => (),
-                DefKind::Enum => (),
-                DefKind::Struct => (),
-                DefKind::Union => (),
-                DefKind::Trait => (),
-                DefKind::Function => (),
-                DefKind::Macro => (),
+                DefKind::Trait => {
+                    let methods = DocData::build_methods(config, def, data)?;
+                    data.insert(
+                        def.qualname.clone(),
+                        Item::Trait {
+                            meta: meta(&def.value),
+                            methods,
+                        },
+                    );
+                }
+                DefKind::Function => {
+                    data.insert(def.qualname.clone(), Item::Function { meta: meta(&def.value) });
+                }
+                DefKind::Const => {
+                    data.insert(def.qualname.clone(), Item::Const { meta: meta(&def.value) });
+                }
+                DefKind::Static => {
+                    data.insert(def.qualname.clone(), Item::Static { meta: meta(&def.value) });
+                }
+                DefKind::Type => {
+                    data.insert(def.qualname.clone(), Item::Type { meta: meta(&def.value) });
+                }
+                DefKind::Macro => {
+                    data.insert(def.qualname.clone(), Item::Macro { meta: meta(&def.value) });
+                }
+                // Inherent methods (`impl Foo { fn bar() {} }`) surface as
+                // direct children of the enclosing module, not of some
+                // separate impl def, so there's nothing here yet to resolve
+                // the owning struct from. Collect them and attach them once
+                // every module (and so every struct) has been visited.
+                DefKind::Method => pending_methods.push(def.clone()),
                 DefKind::Tuple => (),
-                DefKind::Method => (),
-                DefKind::Type => (),
                 DefKind::Local => (),
                 DefKind::Field => (),
             }
         }
 
-        Ok(data)
+        Ok(())
     }
 
-    fn to_json(&self, config: &Config) -> Result<String, Box<std::error::Error>> {
-        use jsonapi::api::*;
+    /// Resolves the owning struct of each inherent method found while walking
+    /// modules, recording it the same way a trait's methods are recorded by
+    /// `build_methods`: an `Item::Method` in `data`, plus its qualname added
+    /// to the owning struct's `methods`. Methods whose owner isn't a struct
+    /// (or whose owner couldn't be resolved) are dropped.
+    fn attach_methods(
+        config: &Config,
+        data: &mut HashMap<String, Item>,
+        pending_methods: Vec<analysis::raw::Def>,
+    ) -> Result<(), Box<std::error::Error>> {
+        for method in pending_methods {
+            let owner_id = match method.parent {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let owner = config.host.get_def(owner_id)?;
+            if owner.kind != DefKind::Struct {
+                continue;
+            }
 
-        let root_def = config.host.get_def(self.krate.id)?;
+            data.insert(
+                method.qualname.clone(),
+                Item::Method {
+                    meta: ItemMeta {
+                        name: method.name.clone(),
+                        docs: method.docs.clone(),
+                        signature: method.value.clone(),
+                        parent: owner.qualname.clone(),
+                        parent_kind: "struct",
+                    },
+                },
+            );
+
+            if let Some(&mut Item::Struct { ref mut methods, .. }) = data.get_mut(&owner.qualname) {
+                methods.push(method.qualname.clone());
+            }
+        }
 
-        let mut document = JsonApiDocument::default();
+        Ok(())
+    }
 
-        let mut map = HashMap::new();
-        map.insert(
-            String::from("docs"),
-            serde_json::Value::String(root_def.docs.clone()),
-        );
+    /// Recurses into a trait's children to pick up its methods, inserting each
+    /// one into `data` and returning the qualnames of the methods found.
+    fn build_methods(
+        config: &Config,
+        trait_def: &analysis::raw::Def,
+        data: &mut HashMap<String, Item>,
+    ) -> Result<Vec<String>, Box<std::error::Error>> {
+        let children = config.host.for_each_child_def(
+            trait_def.id,
+            |_, def| def.clone(),
+        )?;
 
-        let mut relationships = HashMap::new();
+        let mut methods = Vec::new();
+        for method in children.iter().filter(|child| child.kind == DefKind::Method) {
+            methods.push(method.qualname.clone());
+            data.insert(
+                method.qualname.clone(),
+                Item::Method {
+                    meta: ItemMeta {
+                        name: method.name.clone(),
+                        docs: method.docs.clone(),
+                        signature: method.value.clone(),
+                        parent: trait_def.qualname.clone(),
+                        parent_kind: "trait",
+                    },
+                },
+            );
+        }
 
-        let mut relationship = Relationship {
-            data: IdentifierData::Multiple(Vec::new()),
-            links: None,
-        };
+        Ok(methods)
+    }
+
+    /// The qualnames of `id`'s children of kind `kind` (e.g. a struct's fields).
+    fn child_names(
+        config: &Config,
+        id: analysis::Id,
+        kind: DefKind,
+    ) -> Result<Vec<String>, Box<std::error::Error>> {
+        let children = config.host.for_each_child_def(id, |_, def| def.clone())?;
+
+        Ok(
+            children
+                .into_iter()
+                .filter(|def| def.kind == kind)
+                .map(|def| def.qualname)
+                .collect(),
+        )
+    }
+
+    fn to_json(&self, config: &Config) -> Result<String, Box<std::error::Error>> {
+        use jsonapi::api::*;
 
-        //TODO this is bad, use real option handling in the loop
+        let root_def = config.host.get_def(self.krate.id)?;
+
+        let mut document = JsonApiDocument::default();
         document.included = Some(Vec::new());
 
+        // One `Relationship` per item kind, collecting every module (nested
+        // ones included) and every other item that belongs directly to the
+        // crate root.
+        let mut crate_children: HashMap<&'static str, Vec<ResourceIdentifier>> = HashMap::new();
+
         for (id, item) in self.data.iter() {
+            let (type_str, meta) = match item {
+                &Item::Module { .. } => ("module", None),
+                &Item::Struct { ref meta, .. } => ("struct", Some(meta)),
+                &Item::Enum { ref meta } => ("enum", Some(meta)),
+                &Item::Trait { ref meta, .. } => ("trait", Some(meta)),
+                &Item::Function { ref meta } => ("function", Some(meta)),
+                &Item::Method { ref meta } => ("method", Some(meta)),
+                &Item::Const { ref meta } => ("const", Some(meta)),
+                &Item::Static { ref meta } => ("static", Some(meta)),
+                &Item::Type { ref meta } => ("type", Some(meta)),
+                &Item::Union { ref meta, .. } => ("union", Some(meta)),
+                &Item::Macro { ref meta } => ("macro", Some(meta)),
+            };
+
+            let mut attributes = HashMap::new();
+
+            let (name, docs) = match item {
+                &Item::Module { ref name, ref docs } => (name.clone(), docs.clone()),
+                _ => {
+                    let meta = meta.unwrap();
+                    (meta.name.clone(), meta.docs.clone())
+                }
+            };
+            attributes.insert(String::from("name"), serde_json::Value::String(name));
+            attributes.insert(String::from("docs"), serde_json::Value::String(docs));
+
+            if let Some(meta) = meta {
+                attributes.insert(
+                    String::from("signature"),
+                    serde_json::Value::String(meta.signature.clone()),
+                );
+            }
+
             match item {
-                &Item::Module { ref name, ref docs } => {
-                    if let IdentifierData::Multiple(ref mut v) = relationship.data {
-                        v.push(ResourceIdentifier {
-                            _type: String::from("module"),
-                            id: id.clone(),
-                        });
-                    };
-                    let mut map = HashMap::new();
-                    map.insert(
-                        String::from("name"),
-                        serde_json::Value::String(name.clone()),
+                &Item::Struct { ref fields, .. } | &Item::Union { ref fields, .. } => {
+                    attributes.insert(
+                        String::from("fields"),
+                        serde_json::Value::Array(
+                            fields.iter().cloned().map(serde_json::Value::String).collect(),
+                        ),
                     );
-                    map.insert(
-                        String::from("docs"),
-                        serde_json::Value::String(docs.clone()),
+                }
+                _ => (),
+            }
+
+            match item {
+                &Item::Trait { ref methods, .. } | &Item::Struct { ref methods, .. } => {
+                    attributes.insert(
+                        String::from("methods"),
+                        serde_json::Value::Array(
+                            methods.iter().cloned().map(serde_json::Value::String).collect(),
+                        ),
                     );
+                }
+                _ => (),
+            }
 
-                    let module = Resource {
-                        _type: String::from("module"),
-                        id: id.clone(),
-                        attributes: map,
+            // Link every non-module item back to its parent (the crate root or
+            // module containing it, or for a method, the trait or struct it
+            // belongs to).
+            let relationships = meta.map(|meta| {
+                let mut relationships = HashMap::new();
+                relationships.insert(
+                    String::from("parent"),
+                    Relationship {
+                        data: IdentifierData::Single(ResourceIdentifier {
+                            _type: String::from(meta.parent_kind),
+                            id: meta.parent.clone(),
+                        }),
                         links: None,
-                        meta: None,
-                        relationships: None,
-                    };
-
-                    document.included.as_mut().map(|v| v.push(module));
-                }
+                    },
+                );
+                relationships
+            });
+
+            // Every module, nested or not, is listed as a crate child, since a
+            // module records no parent of its own; other items only are
+            // if their parent is the crate root itself rather than some
+            // submodule (a trait's or struct's methods are linked from their
+            // owner's own `methods` attribute instead).
+            let is_crate_child = meta.map(|meta| meta.parent_kind == "crate").unwrap_or(true);
+            if is_crate_child {
+                crate_children.entry(type_str).or_insert_with(Vec::new).push(ResourceIdentifier {
+                    _type: String::from(type_str),
+                    id: id.clone(),
+                });
             }
+
+            let resource = Resource {
+                _type: String::from(type_str),
+                id: id.clone(),
+                attributes,
+                links: None,
+                meta: None,
+                relationships,
+            };
+
+            document.included.as_mut().map(|v| v.push(resource));
+        }
+
+        let mut crate_relationships = HashMap::new();
+        for (type_str, identifiers) in crate_children {
+            crate_relationships.insert(
+                format!("{}s", type_str),
+                Relationship {
+                    data: IdentifierData::Multiple(identifiers),
+                    links: None,
+                },
+            );
         }
 
-        relationships.insert(String::from("modules"), relationship);
+        if !self.deps.is_empty() {
+            let dep_identifiers = self.deps
+                .iter()
+                .map(|dep| ResourceIdentifier { _type: String::from("crate"), id: dep.clone() })
+                .collect();
+
+            crate_relationships.insert(
+                String::from("dependencies"),
+                Relationship {
+                    data: IdentifierData::Multiple(dep_identifiers),
+                    links: None,
+                },
+            );
+        }
+
+        let mut crate_attributes = HashMap::new();
+        crate_attributes.insert(
+            String::from("docs"),
+            serde_json::Value::String(root_def.docs.clone()),
+        );
 
         let len = root_def.qualname.len();
         let krate = Resource {
             _type: String::from("crate"),
             // example:: -> example
             id: root_def.qualname[..(len - 2)].to_string(),
-            attributes: map,
+            attributes: crate_attributes,
             links: None,
             meta: None,
-            relationships: Some(relationships),
+            relationships: Some(crate_relationships),
         };
 
         document.data = Some(PrimaryData::Single(Box::new(krate)));
@@ -351,7 +833,28 @@ struct Crate {
     modules: Vec<String>,
 }
 
+/// Attributes shared by every non-module item: its own name/docs/signature,
+/// plus enough to link it back to whatever contains it.
+#[derive(Debug, Clone)]
+struct ItemMeta {
+    name: String,
+    docs: String,
+    signature: String,
+    parent: String,
+    parent_kind: &'static str,
+}
+
 #[derive(Debug)]
 enum Item {
     Module { name: String, docs: String },
+    Struct { meta: ItemMeta, fields: Vec<String>, methods: Vec<String> },
+    Enum { meta: ItemMeta },
+    Trait { meta: ItemMeta, methods: Vec<String> },
+    Function { meta: ItemMeta },
+    Method { meta: ItemMeta },
+    Const { meta: ItemMeta },
+    Static { meta: ItemMeta },
+    Type { meta: ItemMeta },
+    Union { meta: ItemMeta, fields: Vec<String> },
+    Macro { meta: ItemMeta },
 }
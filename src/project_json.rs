@@ -0,0 +1,90 @@
+//! Support for documenting crates described by a `rust-project.json` file,
+//! modeled on rust-analyzer's `ProjectJson`/`ProjectJsonData`. This lets build
+//! systems other than Cargo (Bazel, Buck, ...) describe their crate graph
+//! directly instead of us having to understand their build files.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde_json;
+use serde_json::Value;
+
+/// A single dependency edge: `krate` is the index of the dependency within the
+/// `crates` array of the `ProjectJson` it belongs to.
+#[derive(Debug, Clone)]
+pub struct Dep {
+    pub krate: usize,
+    pub name: String,
+}
+
+/// One crate as described by `rust-project.json`.
+#[derive(Debug, Clone)]
+pub struct Crate {
+    pub root_module: PathBuf,
+    pub edition: String,
+    pub deps: Vec<Dep>,
+}
+
+impl Crate {
+    /// The name we document this crate under. `rust-project.json` has no
+    /// required `name` field, so we use the root module's file stem (so a
+    /// root module at `src/lib.rs` is documented as `lib`).
+    pub fn name(&self) -> String {
+        self.root_module
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// A parsed `rust-project.json`: the crates that make up the project, described
+/// directly rather than discovered via `cargo metadata`.
+#[derive(Debug, Clone)]
+pub struct ProjectJson {
+    pub crates: Vec<Crate>,
+}
+
+impl ProjectJson {
+    pub fn from_path(path: &Path) -> Result<ProjectJson, Box<std::error::Error>> {
+        let mut file = File::open(path)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+
+        let json: Value = serde_json::from_str(&contents)?;
+        ProjectJson::from_json(&json)
+    }
+
+    fn from_json(json: &Value) -> Result<ProjectJson, Box<std::error::Error>> {
+        let mut crates = Vec::new();
+
+        let raw_crates = json["crates"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        for krate in raw_crates {
+            let root_module = match krate["root_module"].as_str() {
+                Some(root_module) => PathBuf::from(root_module),
+                None => return Err("crate entry is missing \"root_module\"".into()),
+            };
+
+            let mut deps = Vec::new();
+            let raw_deps = krate["deps"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+            for dep in raw_deps {
+                let krate = match dep["crate"].as_u64() {
+                    Some(krate) => krate as usize,
+                    None => return Err("dep entry is missing \"crate\"".into()),
+                };
+                deps.push(Dep {
+                    krate,
+                    name: dep["name"].as_str().unwrap_or_default().to_string(),
+                });
+            }
+
+            crates.push(Crate {
+                root_module,
+                edition: krate["edition"].as_str().unwrap_or("2015").to_string(),
+                deps,
+            });
+        }
+
+        Ok(ProjectJson { crates })
+    }
+}